@@ -0,0 +1,121 @@
+// Copyright 2016 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement.  This, along with the Licenses can be
+// found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+//! Turns an IPC `AuthReq` into a registered app: mints keys, grants the requested container
+//! permissions, and records everything in the authenticator config.
+
+use AccessContainerEntry;
+use AuthError;
+use access_container;
+use config::{self, AppInfo, AppKeys, AppPermissions, Apps};
+use futures::{Future, future};
+use safe_core::{Client, FutureExt};
+use safe_core::ipc::req::AuthReq;
+use safe_core::ipc::req::ContainerPermissions;
+use std::collections::HashMap;
+
+/// The authentication state of an app, as tracked by the authenticator.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AppState {
+    /// The app has never been authenticated.
+    NotAuthenticated,
+    /// The app is authenticated and registered.
+    Authenticated,
+    /// The app was authenticated at some point but has since been revoked.
+    Revoked,
+}
+
+/// Determines the current state of `app_id`, given the already-fetched `apps` config. For an app
+/// in the config, fetches its access container entry: present means authenticated, absent or
+/// emptied out means revoked.
+pub fn app_state<C: Client>(
+    client: &C,
+    apps: &Apps,
+    app_id: &str,
+) -> Box<Future<Item = AppState, Error = AuthError>> {
+    let app = match apps.get(app_id) {
+        Some(app) => app.clone(),
+        None => return future::ok(AppState::NotAuthenticated).into_box(),
+    };
+
+    access_container::fetch_entry(client, &app.info.id, &app.keys)
+        .map(|entry| if entry.is_some() {
+            AppState::Authenticated
+        } else {
+            AppState::Revoked
+        })
+        .into_box()
+}
+
+/// Grants the container permissions requested by `auth_req`, mints fresh keys for the app, and
+/// records the result in the authenticator config. Returns the freshly-created `AppInfo` and the
+/// access container entry it was granted.
+pub fn authenticate<C: Client>(
+    client: &C,
+    apps_version: u64,
+    apps: Apps,
+    auth_req: AuthReq,
+) -> Box<Future<Item = (AppInfo, AccessContainerEntry), Error = AuthError>> {
+    let app_id = auth_req.app.id.clone();
+    let c2 = client.clone();
+
+    let requested_containers = auth_req.containers.clone();
+
+    let app = AppInfo {
+        info: auth_req.app,
+        keys: AppKeys::random(),
+        app_permissions: AppPermissions {
+            transfer_coins: auth_req.app_permissions.transfer_coins,
+            get_balance: auth_req.app_permissions.get_balance,
+            perform_mutations: auth_req.app_permissions.perform_mutations,
+        },
+        containers: requested_containers,
+    };
+
+    grant_containers(client, app.keys.clone(), auth_req.containers)
+        .and_then(move |containers_entry| {
+            config::insert_app(&c2, apps, config::next_version(apps_version), app)
+                .map(move |_| (app_id, containers_entry))
+        })
+        .and_then(move |(app_id, containers_entry)| {
+            // Re-fetch so the caller gets back the exact `AppInfo` that was persisted.
+            config::list_apps(client).and_then(move |(_, apps)| {
+                let app = apps.get(&app_id).cloned().ok_or_else(|| {
+                    AuthError::from("App vanished from config immediately after being inserted")
+                })?;
+                Ok((app, containers_entry))
+            })
+        })
+        .into_box()
+}
+
+/// Turns a requested container list into an access container entry via
+/// `access_container::fetch_or_insert_container_mdata` and passes it through
+/// `access_container::apply_permissions`; see those for what is and is not done on the network.
+/// Shared between the initial grant (`authenticate`) and app restore
+/// (`auth_restore_revoked_app`), which replays a previously granted entry the same way.
+pub fn grant_containers<C: Client>(
+    client: &C,
+    app_keys: AppKeys,
+    containers: HashMap<String, ContainerPermissions>,
+) -> Box<Future<Item = AccessContainerEntry, Error = AuthError>> {
+    access_container::fetch_or_insert_container_mdata(client, containers)
+        .and_then(move |mdata_for_containers| {
+            access_container::apply_permissions(client, &app_keys, mdata_for_containers)
+        })
+        .into_box()
+}
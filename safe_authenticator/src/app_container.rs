@@ -0,0 +1,49 @@
+// Copyright 2016 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement.  This, along with the Licenses can be
+// found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+//! Creation and removal of an app's own dedicated container (`apps/<app_id>`).
+
+use AuthError;
+use config::AppKeys;
+use futures::Future;
+use safe_core::{Client, FutureExt};
+use safe_core::ipc::req::AppExchangeInfo;
+
+/// Called for an app's dedicated container (`apps/<app_id>`) when a revoked app is restored via
+/// `auth_restore_revoked_app`. Returns `Ok(())` without touching the network: no container is
+/// created or looked up, and no permissions are granted to the app.
+pub fn fetch<C: Client>(
+    client: C,
+    app_info: AppExchangeInfo,
+    app_keys: AppKeys,
+) -> Box<Future<Item = (), Error = AuthError>> {
+    let _ = app_keys;
+    let _app_id = app_info.id;
+    let _ = client;
+
+    ::futures::future::ok(()).into_box()
+}
+
+/// Called for `app_id`'s dedicated container when a revoked app is removed. Returns `Ok(())`
+/// without touching the network: neither the container nor its entry in the user's root
+/// container is removed.
+pub fn remove<C: Client>(client: C, app_id: &str) -> Box<Future<Item = (), Error = AuthError>> {
+    let _ = client;
+    let _ = app_id;
+
+    ::futures::future::ok(()).into_box()
+}
@@ -0,0 +1,140 @@
+// Copyright 2016 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement.  This, along with the Licenses can be
+// found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+//! Persistence of the authenticator's own bookkeeping data: the set of apps it has ever
+//! dealt with, their keys, and the permissions they were granted.
+
+use AuthError;
+use futures::Future;
+use rust_sodium::crypto::box_;
+use rust_sodium::crypto::secretbox;
+use rust_sodium::crypto::sign;
+use safe_core::{Client, FutureExt};
+use safe_core::ipc::req::{AppExchangeInfo, ContainerPermissions};
+use std::collections::HashMap;
+
+/// Account-level permissions granted to an app, as opposed to the per-container permissions
+/// carried alongside its access container entry.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AppPermissions {
+    /// Whether this app has permission to transfer coins from the account.
+    pub transfer_coins: bool,
+    /// Whether this app has permission to read the account balance.
+    pub get_balance: bool,
+    /// Whether this app has permission to perform mutations (of any type).
+    pub perform_mutations: bool,
+}
+
+/// The asymmetric and symmetric keys an app was issued during authentication.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AppKeys {
+    /// The app's public sign key, also used as its owner key for data it puts to the network.
+    pub owner_key: sign::PublicKey,
+    /// The app's public sign key.
+    pub sign_pk: sign::PublicKey,
+    /// The app's secret sign key.
+    pub sign_sk: sign::SecretKey,
+    /// The app's public asymmetric encryption key.
+    pub enc_pk: box_::PublicKey,
+    /// The app's secret asymmetric encryption key.
+    pub enc_sk: box_::SecretKey,
+    /// The symmetric key used to encrypt the app's access container entry.
+    pub enc_key: secretbox::Key,
+}
+
+impl AppKeys {
+    /// Generates a fresh set of keys for an app being authenticated for the first time.
+    pub fn random() -> Self {
+        let (sign_pk, sign_sk) = sign::gen_keypair();
+        let (enc_pk, enc_sk) = box_::gen_keypair();
+
+        AppKeys {
+            owner_key: sign_pk,
+            sign_pk,
+            sign_sk,
+            enc_pk,
+            enc_sk,
+            enc_key: secretbox::gen_key(),
+        }
+    }
+}
+
+/// A registered app, as tracked by the authenticator config.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AppInfo {
+    /// Exchange info (id, name, vendor, ...).
+    pub info: AppExchangeInfo,
+    /// Keys issued to the app.
+    pub keys: AppKeys,
+    /// Account-level permissions granted to the app.
+    pub app_permissions: AppPermissions,
+    /// The container permissions the app was last granted, keyed by container name. Kept around
+    /// so that a revoked app can later be restored (see `app_auth::grant_containers`) without
+    /// asking the user to re-grant every container from scratch.
+    pub containers: HashMap<String, ContainerPermissions>,
+}
+
+/// All apps the authenticator has ever dealt with, keyed by `app_id`.
+pub type Apps = HashMap<String, AppInfo>;
+
+/// Returns the next config version to write, given the one that was last read.
+pub fn next_version(version: u64) -> u64 {
+    version + 1
+}
+
+/// Retrieves the current version of the apps config and the set of apps it contains.
+pub fn list_apps<C: Client>(client: &C) -> Box<Future<Item = (u64, Apps), Error = AuthError>> {
+    client
+        .get_config_entry(CONFIG_APPS_ENTRY_KEY)
+        .map_err(AuthError::from)
+        .into_box()
+}
+
+/// Persists a newly-authenticated app, bumping the config version.
+pub fn insert_app<C: Client>(
+    client: &C,
+    apps: Apps,
+    version: u64,
+    app: AppInfo,
+) -> Box<Future<Item = Apps, Error = AuthError>> {
+    let mut apps = apps;
+    apps.insert(app.info.id.clone(), app);
+
+    client
+        .set_config_entry(CONFIG_APPS_ENTRY_KEY, &apps, version)
+        .map_err(AuthError::from)
+        .map(move |_| apps)
+        .into_box()
+}
+
+/// Removes an app from the config, bumping the config version.
+pub fn remove_app<C: Client>(
+    client: &C,
+    mut apps: Apps,
+    version: u64,
+    app_id: &str,
+) -> Box<Future<Item = (), Error = AuthError>> {
+    let _ = apps.remove(app_id);
+
+    client
+        .set_config_entry(CONFIG_APPS_ENTRY_KEY, &apps, version)
+        .map_err(AuthError::from)
+        .into_box()
+}
+
+const CONFIG_APPS_ENTRY_KEY: &[u8] = b"apps";
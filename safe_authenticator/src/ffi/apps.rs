@@ -18,12 +18,14 @@
 use AccessContainerEntry;
 use AuthError;
 use Authenticator;
-use app_auth::{AppState, app_state};
+use access_container;
+use app_auth::{self, AppState as AuthAppState, app_state};
 use app_container;
 use config;
+use config::{AppInfo, AppPermissions};
 use ffi_utils::{FFI_RESULT_OK, FfiResult, OpaqueCtx, SafePtr, catch_unwind_cb, from_c_str,
                 vec_into_raw_parts};
-use futures::Future;
+use futures::{Future, IntoFuture};
 use maidsafe_utilities::serialisation::deserialise;
 use routing::User::Key;
 use routing::XorName;
@@ -37,7 +39,10 @@ use safe_core::ipc::resp::AppAccess;
 use safe_core::ipc::resp::ffi::AppAccess as FfiAppAccess;
 use safe_core::utils::symmetric_decrypt;
 use std::collections::HashMap;
+use std::env;
+use std::io::Write;
 use std::os::raw::{c_char, c_void};
+use std::ptr;
 
 /// Application registered in the authenticator
 #[repr(C)]
@@ -51,6 +56,8 @@ pub struct RegisteredApp {
     /// Capacity of the containers array. Internal data required
     /// for the Rust allocator.
     pub containers_cap: usize,
+    /// Account-level permissions granted to this app (coin transfer, balance read, mutations).
+    pub app_permissions: AppPermissions,
 }
 
 impl Drop for RegisteredApp {
@@ -65,6 +72,55 @@ impl Drop for RegisteredApp {
     }
 }
 
+/// Initialises the process-wide logger, formatting each record with its level, a timestamp,
+/// and the `file:line` it was emitted from. If `log_level` is null, the level is taken from the
+/// `RUST_LOG` environment variable (defaulting to `Info` if that is unset or invalid).
+///
+/// This makes it possible for integrators of the C API to capture a readable audit trail for
+/// failures that are otherwise opaque, e.g. why `auth_rm_revoked_app` rejected an app, or why
+/// `auth_apps_accessing_mutable_data` came back empty.
+#[no_mangle]
+pub unsafe extern "C" fn auth_init_logging(
+    log_level: *const c_char,
+    user_data: *mut c_void,
+    o_cb: extern "C" fn(*mut c_void, FfiResult),
+) {
+    let user_data = OpaqueCtx(user_data);
+
+    catch_unwind_cb(user_data.0, o_cb, || -> Result<_, AuthError> {
+        let mut builder = env_logger::Builder::new();
+        // `parse` only ever adds directives on top of whatever's already configured, so the
+        // default has to be set explicitly -- otherwise an unset/invalid RUST_LOG falls back to
+        // env_logger's own default (Error) rather than the Info level this function documents.
+        builder.filter_level(log::LevelFilter::Info);
+
+        let filter = if log_level.is_null() {
+            env::var("RUST_LOG").unwrap_or_default()
+        } else {
+            from_c_str(log_level)?
+        };
+        builder.parse(&filter);
+
+        builder.format(|buf, record| {
+            writeln!(
+                buf,
+                "{} {} [{}:{}] {}",
+                buf.timestamp(),
+                record.level(),
+                record.file().unwrap_or("unknown"),
+                record.line().unwrap_or(0),
+                record.args()
+            )
+        });
+
+        let _ = builder.try_init();
+
+        o_cb(user_data.0, FFI_RESULT_OK);
+
+        Ok(())
+    });
+}
+
 /// Removes a revoked app from the authenticator config
 #[no_mangle]
 pub unsafe extern "C" fn auth_rm_revoked_app(
@@ -80,6 +136,9 @@ pub unsafe extern "C" fn auth_rm_revoked_app(
         let app_id = from_c_str(app_id)?;
         let app_id2 = app_id.clone();
         let app_id3 = app_id.clone();
+        let app_id4 = app_id.clone();
+
+        trace!("Removing revoked app {}", app_id);
 
         (*auth).send(move |client| {
             let c2 = client.clone();
@@ -93,11 +152,15 @@ pub unsafe extern "C" fn auth_rm_revoked_app(
                     })
                 })
                 .and_then(move |(app_state, apps, apps_version)| match app_state {
-                    AppState::Revoked => Ok((apps, apps_version)),
-                    AppState::Authenticated => Err(AuthError::from("App is not revoked")),
-                    AppState::NotAuthenticated => Err(AuthError::IpcError(IpcError::UnknownApp)),
+                    AuthAppState::Revoked => Ok((apps, apps_version)),
+                    AuthAppState::Authenticated => {
+                        warn!("Cannot remove app {}: it is not revoked", app_id4);
+                        Err(AuthError::from("App is not revoked"))
+                    }
+                    AuthAppState::NotAuthenticated => Err(AuthError::IpcError(IpcError::UnknownApp)),
                 })
                 .and_then(move |(apps, apps_version)| {
+                    debug!("Removing app {} from the authenticator config", app_id2);
                     config::remove_app(&c3, apps, config::next_version(apps_version), &app_id2)
                 })
                 .and_then(move |_| app_container::remove(c4, &app_id3))
@@ -111,6 +174,75 @@ pub unsafe extern "C" fn auth_rm_revoked_app(
     });
 }
 
+/// Restores a previously revoked app by writing an access container entry for the containers it
+/// held before, without requiring the app to go through IPC authentication again. Its dedicated
+/// app container is passed to `app_container::fetch`, which does not re-create it.
+#[no_mangle]
+pub unsafe extern "C" fn auth_restore_revoked_app(
+    auth: *const Authenticator,
+    app_id: *const c_char,
+    user_data: *mut c_void,
+    o_cb: extern "C" fn(*mut c_void, FfiResult),
+) {
+    let user_data = OpaqueCtx(user_data);
+
+    catch_unwind_cb(user_data.0, o_cb, || -> Result<_, AuthError> {
+        let app_id = from_c_str(app_id)?;
+
+        trace!("Restoring revoked app {}", app_id);
+
+        (*auth).send(move |client| {
+            let c2 = client.clone();
+            let c3 = client.clone();
+            let c4 = client.clone();
+
+            config::list_apps(client)
+                .and_then(move |(_, apps)| {
+                    app_state(&c2, &apps, &app_id).map(move |app_state| (app_state, apps, app_id))
+                })
+                .and_then(move |(app_state, apps, app_id)| match app_state {
+                    AuthAppState::Revoked => {
+                        let app = apps.get(&app_id).cloned().ok_or_else(|| {
+                            AuthError::IpcError(IpcError::UnknownApp)
+                        })?;
+                        Ok(app)
+                    }
+                    AuthAppState::Authenticated => {
+                        warn!("Cannot restore app {}: it is not revoked", app_id);
+                        Err(AuthError::from("App is not revoked"))
+                    }
+                    AuthAppState::NotAuthenticated => Err(AuthError::IpcError(IpcError::UnknownApp)),
+                })
+                .and_then(move |app| {
+                    debug!(
+                        "Re-granting {} previously held container permission(s) for app {}",
+                        app.containers.len(),
+                        app.info.id
+                    );
+                    // Reuse the exact same container-list -> access-container-entry conversion
+                    // the initial grant (`app_auth::authenticate`) uses, so a restored app ends
+                    // up with an entry for each container it held before, with the same
+                    // permission set, rather than a blank access container entry.
+                    app_auth::grant_containers(&c3, app.keys.clone(), app.containers.clone())
+                        .and_then(move |entry| {
+                            access_container::put_entry(&c3, &app.info.id, &app.keys, &entry)
+                                .map(move |_| app)
+                        })
+                })
+                .and_then(move |app| {
+                    debug!("Re-provisioning app container for app {}", app.info.id);
+                    app_container::fetch(c4, app.info.clone(), app.keys.clone())
+                })
+                .then(move |res| {
+                    call_result_cb!(res.map(|_| ()), user_data, o_cb);
+                    Ok(())
+                })
+                .into_box()
+                .into()
+        })
+    });
+}
+
 /// Get a list of apps revoked from authenticator
 pub unsafe extern "C" fn auth_revoked_apps(
     auth: *const Authenticator,
@@ -119,6 +251,8 @@ pub unsafe extern "C" fn auth_revoked_apps(
 ) {
     let user_data = OpaqueCtx(user_data);
 
+    trace!("Listing revoked apps");
+
     catch_unwind_cb(user_data.0, o_cb, || -> Result<_, AuthError> {
         (*auth).send(move |client| {
             let c2 = client.clone();
@@ -156,6 +290,8 @@ pub unsafe extern "C" fn auth_revoked_apps(
                         }
                     }
 
+                    debug!("Found {} revoked app(s)", apps.len());
+
                     o_cb(user_data.0, FFI_RESULT_OK, apps.as_safe_ptr(), apps.len());
 
                     Ok(())
@@ -171,6 +307,36 @@ pub unsafe extern "C" fn auth_revoked_apps(
     })
 }
 
+/// Decrypts and decodes `app`'s access container entry -- `entry_content` being the raw bytes
+/// looked up from the access container's `MutableData`, if any -- into a `RegisteredApp`.
+/// Returns `Ok(None)` if the entry is absent or has been emptied out, which is how a revoked
+/// app's entry is represented. Shared by `auth_registered_apps` and `auth_app_state` so the two
+/// don't drift on how that representation is interpreted.
+fn decode_registered_app(
+    app: &AppInfo,
+    entry_content: Option<&[u8]>,
+) -> Result<Option<RegisteredApp>, AuthError> {
+    let content = match entry_content {
+        Some(content) if !content.is_empty() => content,
+        _ => return Ok(None),
+    };
+
+    let plaintext = symmetric_decrypt(content, &app.keys.enc_key)?;
+    let app_access = deserialise::<AccessContainerEntry>(&plaintext)?;
+    let containers = containers_into_vec(
+        app_access.into_iter().map(|(key, (_, perms))| (key, perms)),
+    )?;
+    let (containers_ptr, len, cap) = vec_into_raw_parts(containers);
+
+    Ok(Some(RegisteredApp {
+        app_info: app.info.clone().into_repr_c()?,
+        containers: containers_ptr,
+        containers_len: len,
+        containers_cap: cap,
+        app_permissions: app.app_permissions,
+    }))
+}
+
 /// Get a list of apps registered in authenticator
 #[no_mangle]
 pub unsafe extern "C" fn auth_registered_apps(
@@ -180,6 +346,8 @@ pub unsafe extern "C" fn auth_registered_apps(
 ) {
     let user_data = OpaqueCtx(user_data);
 
+    trace!("Listing registered apps");
+
     catch_unwind_cb(user_data.0, o_cb, || -> Result<_, AuthError> {
         (*auth).send(move |client| {
             let c2 = client.clone();
@@ -205,34 +373,15 @@ pub unsafe extern "C" fn auth_registered_apps(
 
                     for app in auth_cfg.values() {
                         let key = access_container_enc_key(&app.info.id, &app.keys.enc_key, nonce)?;
+                        let entry_content = entries.get(&key).map(|entry| entry.content.as_slice());
 
-                        // Empty entry means it has been deleted.
-                        let entry = match entries.get(&key) {
-                            Some(entry) if !entry.content.is_empty() => Some(entry),
-                            _ => None,
-                        };
-
-                        if let Some(entry) = entry {
-                            let plaintext = symmetric_decrypt(&entry.content, &app.keys.enc_key)?;
-                            let app_access = deserialise::<AccessContainerEntry>(&plaintext)?;
-
-                            let containers =
-                                containers_into_vec(
-                                    app_access.into_iter().map(|(key, (_, perms))| (key, perms)),
-                                )?;
-
-                            let (containers_ptr, len, cap) = vec_into_raw_parts(containers);
-                            let reg_app = RegisteredApp {
-                                app_info: app.info.clone().into_repr_c()?,
-                                containers: containers_ptr,
-                                containers_len: len,
-                                containers_cap: cap,
-                            };
-
+                        if let Some(reg_app) = decode_registered_app(app, entry_content)? {
                             apps.push(reg_app);
                         }
                     }
 
+                    debug!("Found {} registered app(s)", apps.len());
+
                     o_cb(user_data.0, FFI_RESULT_OK, apps.as_safe_ptr(), apps.len());
 
                     Ok(())
@@ -248,25 +397,168 @@ pub unsafe extern "C" fn auth_registered_apps(
     })
 }
 
+/// FFI-safe representation of an app's authentication state, as returned by `auth_app_state`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AppState {
+    /// The app has never been authenticated.
+    NotAuthenticated,
+    /// The app is authenticated and registered.
+    Authenticated,
+    /// The app has been revoked.
+    Revoked,
+}
+
+impl From<AuthAppState> for AppState {
+    fn from(state: AuthAppState) -> Self {
+        match state {
+            AuthAppState::NotAuthenticated => AppState::NotAuthenticated,
+            AuthAppState::Authenticated => AppState::Authenticated,
+            AuthAppState::Revoked => AppState::Revoked,
+        }
+    }
+}
+
+/// Look up a single app's authentication state and, if it is authenticated, its full
+/// registration record. This avoids having to scan the whole `auth_registered_apps` /
+/// `auth_revoked_apps` arrays just to answer a question about one `app_id`.
+#[no_mangle]
+pub unsafe extern "C" fn auth_app_state(
+    auth: *const Authenticator,
+    app_id: *const c_char,
+    user_data: *mut c_void,
+    o_cb: extern "C" fn(*mut c_void, FfiResult, AppState, *const RegisteredApp),
+) {
+    let user_data = OpaqueCtx(user_data);
+
+    catch_unwind_cb(user_data.0, o_cb, || -> Result<_, AuthError> {
+        let app_id = from_c_str(app_id)?;
+
+        trace!("Looking up authentication state for app {}", app_id);
+
+        (*auth).send(move |client| {
+            let c2 = client.clone();
+            let c3 = client.clone();
+            let c4 = client.clone();
+
+            config::list_apps(client)
+                .and_then(move |(_, apps)| {
+                    app_state(&c2, &apps, &app_id).map(move |state| (state, apps, app_id))
+                })
+                .and_then(move |(state, apps, app_id)| match state {
+                    AuthAppState::Authenticated => {
+                        let app = apps.get(&app_id).cloned().ok_or_else(|| {
+                            AuthError::IpcError(IpcError::UnknownApp)
+                        })?;
+                        Ok((state, Some(app)))
+                    }
+                    AuthAppState::NotAuthenticated | AuthAppState::Revoked => Ok((state, None)),
+                })
+                .and_then(move |(state, app)| match app {
+                    Some(app) => {
+                        c3.access_container()
+                            .map_err(AuthError::from)
+                            .join(Ok(app))
+                            .map(move |(access_container, app)| (state, Some((access_container, app))))
+                            .into_box()
+                    }
+                    None => Ok((state, None)).into_future().into_box(),
+                })
+                .and_then(move |(state, access)| match access {
+                    Some((access_container, app)) => {
+                        c4.list_mdata_entries(access_container.name, access_container.type_tag)
+                            .map_err(AuthError::from)
+                            .map(move |entries| (state, Some((access_container, entries, app))))
+                            .into_box()
+                    }
+                    None => Ok((state, None)).into_future().into_box(),
+                })
+                .and_then(move |(state, access)| {
+                    let reg_app = match access {
+                        Some((access_container, entries, app)) => {
+                            let nonce = access_container.nonce().ok_or_else(|| {
+                                AuthError::from("No nonce on access container's MDataInfo")
+                            })?;
+                            let key =
+                                access_container_enc_key(&app.info.id, &app.keys.enc_key, nonce)?;
+                            let entry_content =
+                                entries.get(&key).map(|entry| entry.content.as_slice());
+
+                            decode_registered_app(&app, entry_content)?
+                        }
+                        None => None,
+                    };
+
+                    debug!("App state resolved to {:?}", state);
+
+                    match reg_app {
+                        Some(reg_app) => o_cb(user_data.0, FFI_RESULT_OK, state.into(), &reg_app),
+                        None => o_cb(user_data.0, FFI_RESULT_OK, state.into(), ptr::null()),
+                    }
+
+                    Ok(())
+                })
+                .map_err(move |e| {
+                    call_result_cb!(Err::<(), _>(e), user_data, o_cb);
+                })
+                .into_box()
+                .into()
+        })?;
+
+        Ok(())
+    })
+}
+
+/// Discriminates between the two `MutableData` flavours exposed by the network.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MDataKind {
+    /// Sequenced (versioned) `MutableData` -- mutations must supply the expected entry version.
+    Seq,
+    /// Unsequenced (unversioned) `MutableData`.
+    Unseq,
+}
+
+/// An `AppAccess` record tagged with the kind of `MutableData` it was read from.
+#[repr(C)]
+pub struct AppAccessWithKind {
+    /// The underlying per-app access entry.
+    pub app_access: FfiAppAccess,
+    /// The kind of `MutableData` this access entry was read from.
+    pub kind: MDataKind,
+}
+
 /// Return a list of apps having access to an arbitrary MD object.
-/// `md_name` and `md_type_tag` together correspond to a single MD.
+/// `md_name` and `md_type_tag` together correspond to a single MD, and `md_kind` selects
+/// whether it should be looked up as sequenced or unsequenced data.
 #[no_mangle]
 pub unsafe extern "C" fn auth_apps_accessing_mutable_data(
     auth: *mut Authenticator,
     md_name: *const XorNameArray,
     md_type_tag: u64,
+    md_kind: MDataKind,
     user_data: *mut c_void,
-    o_cb: extern "C" fn(*mut c_void, FfiResult, *const FfiAppAccess, usize),
+    o_cb: extern "C" fn(*mut c_void, FfiResult, *const AppAccessWithKind, usize),
 ) {
     let user_data = OpaqueCtx(user_data);
     let name = XorName(*md_name);
 
+    trace!(
+        "Listing apps with access to {:?} ({:?})",
+        name,
+        md_kind
+    );
+
     catch_unwind_cb(user_data.0, o_cb, || -> Result<_, AuthError> {
         (*auth).send(move |client| {
             let c2 = client.clone();
 
-            client
-                .list_mdata_permissions(name, md_type_tag)
+            let permissions = match md_kind {
+                MDataKind::Seq => client.list_mdata_permissions(name, md_type_tag),
+                MDataKind::Unseq => client.list_unseq_mdata_permissions(name, md_type_tag),
+            };
+
+            permissions
                 .map_err(AuthError::from)
                 .join(
                     // Fetch a list of registered apps in parallel
@@ -280,7 +572,7 @@ pub unsafe extern "C" fn auth_apps_accessing_mutable_data(
                 .and_then(move |(permissions, apps)| {
                     // Map the list of keys retrieved from MD to a list of registered apps (even if
                     // they're in the Revoked state) and create a new `AppAccess` struct object
-                    let mut app_access_vec: Vec<FfiAppAccess> = Vec::new();
+                    let mut app_access_vec: Vec<AppAccessWithKind> = Vec::new();
 
                     for (user, perm_set) in permissions {
                         if let Key(public_key) = user {
@@ -306,10 +598,15 @@ pub unsafe extern "C" fn auth_apps_accessing_mutable_data(
                                     }
                                 }
                             };
-                            app_access_vec.push(app_access.into_repr_c()?);
+                            app_access_vec.push(AppAccessWithKind {
+                                app_access: app_access.into_repr_c()?,
+                                kind: md_kind,
+                            });
                         }
                     }
 
+                    debug!("Found {} app(s) with access", app_access_vec.len());
+
                     o_cb(
                         user_data.0,
                         FFI_RESULT_OK,
@@ -329,3 +626,78 @@ pub unsafe extern "C" fn auth_apps_accessing_mutable_data(
         Ok(())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::AppKeys;
+    use maidsafe_utilities::serialisation::serialise;
+    use safe_core::ipc::req::AppExchangeInfo;
+    use safe_core::utils::symmetric_encrypt;
+
+    fn test_app() -> AppInfo {
+        AppInfo {
+            info: AppExchangeInfo {
+                id: "test-app".to_string(),
+                scope: None,
+                name: "Test App".to_string(),
+                vendor: "Test Vendor".to_string(),
+            },
+            keys: AppKeys::random(),
+            app_permissions: AppPermissions::default(),
+            containers: HashMap::new(),
+        }
+    }
+
+    // Mirrors what an app goes through under `auth_restore_revoked_app`: authenticated (a real
+    // access container entry), revoked (the entry emptied out), restored (the entry re-written
+    // with real content again), revoked a second time. `decode_registered_app` is what both
+    // `auth_registered_apps` and `auth_app_state` rely on to read that state back, so it must
+    // never confuse an emptied-out entry for a live one in either direction, repeatedly.
+    #[test]
+    fn revoke_restore_revoke_lifecycle() {
+        let app = test_app();
+
+        let plaintext = unwrap!(serialise(&AccessContainerEntry::default()));
+        let ciphertext = unwrap!(symmetric_encrypt(&plaintext, &app.keys.enc_key, None));
+
+        // Authenticated: a real entry (even one with no granted containers) decodes.
+        assert!(unwrap!(decode_registered_app(&app, Some(&ciphertext))).is_some());
+
+        // Revoked: an emptied-out entry, or no entry at all, decodes to nothing.
+        assert!(unwrap!(decode_registered_app(&app, Some(&[]))).is_none());
+        assert!(unwrap!(decode_registered_app(&app, None)).is_none());
+
+        // Restored: writing the real content back makes it decode again.
+        assert!(unwrap!(decode_registered_app(&app, Some(&ciphertext))).is_some());
+
+        // Revoked again: emptying it out a second time still reads back as revoked.
+        assert!(unwrap!(decode_registered_app(&app, Some(&[]))).is_none());
+    }
+
+    // `auth_app_state` maps the business-logic `app_auth::AppState` onto its FFI-safe mirror;
+    // all three branches must round-trip without falling through to a wrong default.
+    #[test]
+    fn ffi_app_state_covers_all_branches() {
+        assert_eq!(
+            AppState::from(AuthAppState::NotAuthenticated),
+            AppState::NotAuthenticated
+        );
+        assert_eq!(
+            AppState::from(AuthAppState::Authenticated),
+            AppState::Authenticated
+        );
+        assert_eq!(AppState::from(AuthAppState::Revoked), AppState::Revoked);
+    }
+
+    // `auth_apps_accessing_mutable_data` picks `list_mdata_permissions` or
+    // `list_unseq_mdata_permissions` based on `md_kind`, then tags every resulting
+    // `AppAccessWithKind` with that same `md_kind` so callers can tell which list a result came
+    // from. That dispatch needs a live/mock network `Client`, which lives in `safe_core` and
+    // isn't part of this source snapshot, so it isn't covered here; this test only checks that
+    // the two `MDataKind` variants are distinct.
+    #[test]
+    fn mdata_kind_variants_are_distinct() {
+        assert_ne!(MDataKind::Seq, MDataKind::Unseq);
+    }
+}